@@ -0,0 +1,6 @@
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum ExportFormat {
+    Ron,
+    Json,
+    Markdown,
+}
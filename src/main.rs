@@ -1,19 +1,25 @@
 use std::{
+    collections::HashSet,
     fs::File,
     io::{Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use eyre::{Context, OptionExt};
+use export_format::ExportFormat;
 use home::home_dir;
-use work_data_file::{FileVersion, WorkDataFile};
+use priority::Priority;
+use work_data_file::{FileVersion, FileVersionProbe, WorkDataFile};
 use work_entry::WorkEntry;
 use work_entry_id::{WorkEntryId, WorkEntryIdFull};
 use work_entry_status::WorkEntryStatus;
 
+pub mod export_format;
+pub mod priority;
+pub mod time_entry;
 pub mod work_data_file;
 pub mod work_entry;
 pub mod work_entry_id;
@@ -39,6 +45,15 @@ enum WorkAction {
         description: Option<String>,
         /// Optional parent that this will be attached to.
         parent: Option<WorkEntryIdFull>,
+        /// The priority of the entry, defaults to low.
+        #[arg(short, long)]
+        priority: Option<Priority>,
+        /// A tag to attach to the entry, can be provided multiple times.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// The ID of an entry that must be completed first, can be provided multiple times.
+        #[arg(long = "depends-on")]
+        depends_on: Vec<WorkEntryIdFull>,
     },
     /// Edit a work action entry.
     Edit {
@@ -52,12 +67,28 @@ enum WorkAction {
         /// The new status of the entry.
         #[arg(short, long)]
         status: Option<WorkEntryStatus>,
+
+        /// The new priority of the entry.
+        #[arg(short, long)]
+        priority: Option<Priority>,
+
+        /// A tag to add to the entry, can be provided multiple times.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// The ID of an entry that must be completed first, can be provided multiple times.
+        #[arg(long = "depends-on")]
+        depends_on: Vec<WorkEntryIdFull>,
     },
     /// List all unfinished work actions.
     List {
         /// Show all entries, not just completed ones.
         #[arg(short, long, default_value_t = false)]
         all: bool,
+
+        /// Only show entries with the given tag.
+        #[arg(long)]
+        tag: Option<String>,
     },
     /// Show detailed info for an entry.
     Show {
@@ -68,8 +99,26 @@ enum WorkAction {
     Remove { id: WorkEntryIdFull },
     /// Marks the entry with the provided ID as completed.
     Complete { id: WorkEntryIdFull },
-    /// Puts the task with the provided ID at the top of the list.
+    /// Sets the task with the provided ID to the highest priority.
     Prio { id: WorkEntryIdFull },
+    /// Logs time spent on the entry with the provided ID.
+    Log {
+        /// The id of the entry to log time against.
+        id: WorkEntryIdFull,
+        /// Hours spent.
+        hours: u16,
+        /// Minutes spent.
+        minutes: u16,
+    },
+    /// Export the tracked data to another format.
+    Export {
+        /// The format to export to.
+        format: ExportFormat,
+
+        /// Where to write the export, defaults to stdout.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
 }
 
 fn main() -> eyre::Result<()> {
@@ -89,7 +138,7 @@ fn main() -> eyre::Result<()> {
             let latest = wd_file.entries.iter().filter(|e| !e.is_completed()).last();
 
             if let Some(l) = latest {
-                println!("{}", l.to_printable_row());
+                println!("{}", l.to_printable_row(0, None));
             } else {
                 println!("No active tasks, great job!");
             }
@@ -98,36 +147,62 @@ fn main() -> eyre::Result<()> {
             name,
             description,
             parent,
+            priority,
+            tags,
+            depends_on,
         }) => {
             if name.chars().count() > MAX_NAME_LENGTH {
                 eyre::bail!("Name can have at most {MAX_NAME_LENGTH} chars");
             }
 
+            let tags: HashSet<String> = tags.into_iter().collect();
+            let dependencies: HashSet<WorkEntryIdFull> = depends_on.into_iter().collect();
+
             if let Some(parent) = parent {
-                wd_file.add_child_entry(name, description, parent);
+                wd_file.add_child_entry(name, description, parent, priority, tags, dependencies)?;
             } else {
-                wd_file.add_entry(name, description);
+                wd_file.add_entry(name, description, priority, tags, dependencies);
             }
 
             wd_file.save(&config_path).wrap_err("Failed to save file")?;
         }
-        Some(WorkAction::List { all }) => {
-            let entries = wd_file.entries;
-            for entry in entries.iter().rev() {
-                if !all && entry.is_completed() {
-                    continue;
-                }
-
-                println!("{}", entry.to_printable_row());
+        Some(WorkAction::List { all, tag }) => {
+            let mut entries: Vec<&WorkEntry> = wd_file.entries.iter().collect();
+            entries.sort_by(|a, b| {
+                b.priority
+                    .cmp(&a.priority)
+                    .then_with(|| b.modified_at.cmp(&a.modified_at))
+            });
+
+            for entry in entries {
+                print_entry_list(entry, 0, None, all, tag.as_deref());
             }
         }
         Some(WorkAction::Remove { id }) => {
-            let index = wd_file.get_index_for_id(&id)?;
-            wd_file.entries.remove(index);
+            wd_file.remove_entry_full(&id)?;
             wd_file.save(&config_path).wrap_err("Failed to save file")?;
         }
         Some(WorkAction::Complete { id }) => {
-            let entry = wd_file.get_entry_mut(&id)?;
+            let blocking: Vec<String> = wd_file
+                .get_entry_full(&id)?
+                .dependencies
+                .iter()
+                .filter(|dep_id| {
+                    !wd_file
+                        .get_entry_full(dep_id)
+                        .is_ok_and(|dep| dep.is_completed())
+                })
+                .map(|dep_id| dep_id.to_string())
+                .collect();
+
+            if !blocking.is_empty() {
+                eyre::bail!(
+                    "Cannot complete entry, blocked by incomplete dependencies: {}",
+                    blocking.join(", ")
+                );
+            }
+
+            let entry = wd_file.get_entry_full_mut(&id)?;
             eyre::ensure!(
                 !entry.is_completed(),
                 "Entry is already marked as completed"
@@ -138,11 +213,9 @@ fn main() -> eyre::Result<()> {
                 .wrap_err("Failed to save changes")?;
         }
         Some(WorkAction::Prio { id }) => {
-            let index = wd_file.get_index_for_id(&id)?;
-
-            let mut entry = wd_file.entries.remove(index);
+            let entry = wd_file.get_entry_full_mut(&id)?;
+            entry.priority = Priority::High;
             entry.modified_at = Utc::now();
-            wd_file.entries.push(entry);
 
             wd_file
                 .save(&config_path)
@@ -152,59 +225,72 @@ fn main() -> eyre::Result<()> {
             id,
             description,
             status,
+            priority,
+            tags,
+            depends_on,
         }) => {
-            let entry = wd_file.get_entry_mut(&id)?;
-
-            if description.is_none() && status.is_none() {
-                eyre::bail!("No action provided to edit the entry, please provide either description or status (or both)");
+            let entry = wd_file.get_entry_full_mut(&id)?;
+
+            if description.is_none()
+                && status.is_none()
+                && priority.is_none()
+                && tags.is_empty()
+                && depends_on.is_empty()
+            {
+                eyre::bail!("No action provided to edit the entry, please provide either description, status, priority, tag or depends-on");
             }
 
-            entry.description = description;
+            if let Some(description) = description {
+                entry.description = Some(description);
+            }
 
             if let Some(status) = status {
                 entry.status = status;
             }
 
+            if let Some(priority) = priority {
+                entry.priority = priority;
+            }
+
+            entry.tags.extend(tags);
+            entry.dependencies.extend(depends_on);
+
+            wd_file
+                .save(&config_path)
+                .wrap_err("Failed to save changes")?;
+        }
+        Some(WorkAction::Log { id, hours, minutes }) => {
+            let entry = wd_file.get_entry_full_mut(&id)?;
+            entry.log_time(Utc::now().date_naive(), hours, minutes);
             wd_file
                 .save(&config_path)
                 .wrap_err("Failed to save changes")?;
         }
+        Some(WorkAction::Export { format, out }) => {
+            let rendered = match format {
+                ExportFormat::Ron => {
+                    ron::to_string(&wd_file).wrap_err("Failed to serialize to RON")?
+                }
+                ExportFormat::Json => {
+                    serde_json::to_string_pretty(&wd_file).wrap_err("Failed to serialize to JSON")?
+                }
+                ExportFormat::Markdown => wd_file.to_markdown(),
+            };
+
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, rendered).wrap_err("Failed to write export file")?
+                }
+                None => println!("{rendered}"),
+            }
+        }
         Some(WorkAction::Show { id }) => {
-            let Some(WorkEntry {
-                id,
-                name,
-                description,
-                created_at,
-                modified_at,
-                status,
-                children,
-            }) = wd_file.get_entry_or_first(id.as_ref())?
-            else {
+            let Some(entry) = wd_file.get_entry_or_first(id.as_ref())? else {
                 println!("{}", "No unfinished tasks!".bright_green());
                 return Ok(());
             };
 
-            let div = "::".truecolor(175, 175, 175);
-            println!(
-                "{} {div} {} {div} {} {div} {} {div} {} / {}",
-                id.to_string().bright_blue(),
-                name.bright_green(),
-                description
-                    .as_ref()
-                    .unwrap_or(&"<No description>".to_string())
-                    .yellow(),
-                status.to_colored_string(),
-                created_at.to_formatted_string(),
-                modified_at.to_formatted_string()
-            );
-
-            for child in children.iter() {
-                println!(
-                    "{} -- {}",
-                    child.id.to_string().bright_blue(),
-                    child.name.bright_green()
-                )
-            }
+            print_entry_details(entry, None);
         }
     };
 
@@ -221,6 +307,100 @@ impl DisplayableDateTime for DateTime<Utc> {
     }
 }
 
+/// Recursively prints `entry` and its children as indented list rows, each
+/// filtered independently by completion status and the optional tag.
+fn print_entry_list(
+    entry: &WorkEntry,
+    depth: usize,
+    parent_id: Option<&str>,
+    all: bool,
+    tag: Option<&str>,
+) {
+    let matches_tag = tag.is_none_or(|tag| entry.tags.contains(tag));
+
+    if (all || !entry.is_completed()) && matches_tag {
+        println!("{}", entry.to_printable_row(depth, parent_id));
+    }
+
+    let full_id = match parent_id {
+        Some(parent) => format!("{parent}.{}", entry.id),
+        None => entry.id.to_string(),
+    };
+
+    let mut children: Vec<&WorkEntry> = entry.children.iter().collect();
+    children.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| b.modified_at.cmp(&a.modified_at))
+    });
+
+    for child in children {
+        print_entry_list(child, depth + 1, Some(&full_id), all, tag);
+    }
+}
+
+/// Recursively prints `entry`'s detail line followed by the same for every
+/// descendant, addressed by its full dotted ID.
+fn print_entry_details(entry: &WorkEntry, parent_id: Option<&str>) {
+    let full_id = match parent_id {
+        Some(parent) => format!("{parent}.{}", entry.id),
+        None => entry.id.to_string(),
+    };
+
+    let div = "::".truecolor(175, 175, 175);
+    println!(
+        "{} {div} {} {div} {} {div} {} {div} {} {div} {} / {}",
+        full_id.bright_blue(),
+        entry.name.bright_green(),
+        entry
+            .description
+            .as_ref()
+            .unwrap_or(&"<No description>".to_string())
+            .yellow(),
+        entry.priority.to_colored_string(),
+        entry.status.to_colored_string(),
+        entry.created_at.to_formatted_string(),
+        entry.modified_at.to_formatted_string()
+    );
+
+    println!(
+        "  {} {} {} {}",
+        "Logged:".truecolor(175, 175, 175),
+        format_duration(entry.logged_minutes()),
+        "Total:".truecolor(175, 175, 175),
+        format_duration(entry.total_logged_minutes())
+    );
+
+    if !entry.tags.is_empty() {
+        let mut tags: Vec<&String> = entry.tags.iter().collect();
+        tags.sort();
+        println!(
+            "  {} {}",
+            "Tags:".truecolor(175, 175, 175),
+            tags.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    if !entry.dependencies.is_empty() {
+        let mut dependencies: Vec<String> =
+            entry.dependencies.iter().map(|id| id.to_string()).collect();
+        dependencies.sort();
+        println!(
+            "  {} {}",
+            "Depends on:".truecolor(175, 175, 175),
+            dependencies.join(", ")
+        );
+    }
+
+    for child in entry.children.iter() {
+        print_entry_details(child, Some(&full_id));
+    }
+}
+
+fn format_duration(total_minutes: u32) -> String {
+    format!("{}h {}m", total_minutes / 60, total_minutes % 60)
+}
+
 fn get_or_create_file_file(path: &Path) -> eyre::Result<WorkDataFile> {
     if !path.exists() {
         let wd_file = WorkDataFile {
@@ -242,11 +422,28 @@ fn get_or_create_file_file(path: &Path) -> eyre::Result<WorkDataFile> {
     file.read_to_string(&mut buf)
         .wrap_err("Failed to read work file")?;
 
-    let file: WorkDataFile = ron::from_str(&buf).wrap_err("Failed to parse file work entries")?;
+    let probe: FileVersionProbe =
+        ron::from_str(&buf).wrap_err("Failed to parse file version")?;
 
-    eyre::ensure!(
-        file.is_current(),
-        "The stored data file is from an older version, please delete or update it before using the application."
-    );
-    Ok(file)
+    if probe.version == FileVersion::current() {
+        return ron::from_str(&buf).wrap_err("Failed to parse file work entries");
+    }
+
+    let migrated = work_data_file::migrate(&buf, probe.version)
+        .wrap_err("Failed to migrate data file to the current version")?;
+
+    let backup_path = path.with_file_name(format!(
+        "{}.bak-{}",
+        path.file_name()
+            .ok_or_eyre("Data file path has no file name")?
+            .to_string_lossy(),
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    std::fs::copy(path, &backup_path).wrap_err("Failed to back up old data file")?;
+
+    migrated
+        .save(path)
+        .wrap_err("Failed to save migrated data file")?;
+
+    Ok(migrated)
 }
@@ -0,0 +1,22 @@
+use colored::{ColoredString, Colorize};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Default, clap::ValueEnum,
+)]
+pub enum Priority {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn to_colored_string(&self) -> ColoredString {
+        match self {
+            Priority::Low => "Low".green(),
+            Priority::Medium => "Medium".yellow(),
+            Priority::High => "High".red(),
+        }
+    }
+}
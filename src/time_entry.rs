@@ -0,0 +1,29 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// A single block of time logged against a work entry on a given day.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimeEntry {
+    pub logged_date: NaiveDate,
+    pub hours: u16,
+    pub minutes: u16,
+}
+
+impl TimeEntry {
+    pub fn new(logged_date: NaiveDate, hours: u16, minutes: u16) -> Self {
+        let mut entry = Self {
+            logged_date,
+            hours,
+            minutes,
+        };
+        entry.normalize();
+
+        entry
+    }
+
+    /// Carries any whole hours out of `minutes` so it always stays under 60.
+    fn normalize(&mut self) {
+        self.hours += self.minutes / 60;
+        self.minutes %= 60;
+    }
+}
@@ -1,11 +1,14 @@
-use std::{fs::File, io::Write, path::Path};
+use std::{collections::HashSet, fs::File, io::Write, path::Path};
 
+use chrono::{DateTime, Utc};
 use eyre::{Context, ContextCompat};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    priority::Priority,
     work_entry::WorkEntry,
     work_entry_id::{WorkEntryId, WorkEntryIdFull},
+    work_entry_status::WorkEntryStatus,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -19,7 +22,14 @@ impl WorkDataFile {
         self.version == FileVersion::current()
     }
 
-    pub fn add_entry(&mut self, name: String, description: Option<String>) {
+    pub fn add_entry(
+        &mut self,
+        name: String,
+        description: Option<String>,
+        priority: Option<Priority>,
+        tags: HashSet<String>,
+        dependencies: HashSet<WorkEntryIdFull>,
+    ) {
         let highest_num = self
             .entries
             .iter()
@@ -28,7 +38,10 @@ impl WorkDataFile {
             .map(|id| id.next())
             .unwrap_or_default();
 
-        let new_entry = WorkEntry::new(highest_num, name, description);
+        let mut new_entry = WorkEntry::new(highest_num, name, description);
+        new_entry.priority = priority.unwrap_or_default();
+        new_entry.tags = tags;
+        new_entry.dependencies = dependencies;
 
         self.entries.push(new_entry);
     }
@@ -38,8 +51,14 @@ impl WorkDataFile {
         name: String,
         description: Option<String>,
         parent: WorkEntryIdFull,
-    ) {
-        todo!("Not updated");
+        priority: Option<Priority>,
+        tags: HashSet<String>,
+        dependencies: HashSet<WorkEntryIdFull>,
+    ) -> eyre::Result<()> {
+        let parent_entry = self.get_entry_full_mut(&parent)?;
+        parent_entry.add_child(name, description, priority, tags, dependencies);
+
+        Ok(())
     }
 
     pub fn save(&self, path: &Path) -> eyre::Result<()> {
@@ -52,6 +71,17 @@ impl WorkDataFile {
         Ok(())
     }
 
+    /// Renders the tracked entries as a nested Markdown checklist.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        for entry in self.entries.iter() {
+            entry.write_markdown(&mut out, 0);
+        }
+
+        out
+    }
+
     pub fn get_index_for_id(&self, id: &WorkEntryId) -> eyre::Result<usize> {
         let (current_index, _) = self
             .entries
@@ -70,9 +100,12 @@ impl WorkDataFile {
             .wrap_err("Failed to find entry with the provided ID")
     }
 
-    pub fn get_entry_or_first(&self, id: Option<&WorkEntryId>) -> eyre::Result<Option<&WorkEntry>> {
+    pub fn get_entry_or_first(
+        &self,
+        id: Option<&WorkEntryIdFull>,
+    ) -> eyre::Result<Option<&WorkEntry>> {
         if let Some(id) = id {
-            return self.get_entry(id).map(Some);
+            return self.get_entry_full(id).map(Some);
         }
 
         return Ok(self.entries.iter().filter(|e| !e.is_completed()).last());
@@ -84,9 +117,44 @@ impl WorkDataFile {
             .find(|entry| &entry.id == id)
             .wrap_err("Failed to find entry with the provided ID")
     }
+
+    /// Resolves a full, dotted entry ID, descending through `children` for
+    /// each segment past the top level.
+    pub fn get_entry_full(&self, id: &WorkEntryIdFull) -> eyre::Result<&WorkEntry> {
+        let (first, rest) = id.split_first().wrap_err("Cannot look up an empty ID")?;
+        self.get_entry(first)?.resolve_child_path(rest)
+    }
+
+    /// Mutable counterpart of [`WorkDataFile::get_entry_full`].
+    pub fn get_entry_full_mut(&mut self, id: &WorkEntryIdFull) -> eyre::Result<&mut WorkEntry> {
+        let (first, rest) = id.split_first().wrap_err("Cannot look up an empty ID")?;
+        self.get_entry_mut(first)?.resolve_child_path_mut(rest)
+    }
+
+    /// Removes the entry addressed by `id`, which may point at any depth, and
+    /// scrubs it from any other entry's `dependencies` so nothing is left
+    /// permanently blocked on an ID that can no longer resolve.
+    pub fn remove_entry_full(&mut self, id: &WorkEntryIdFull) -> eyre::Result<WorkEntry> {
+        let (first, rest) = id
+            .split_first()
+            .wrap_err("Cannot remove an entry using an empty ID")?;
+
+        let removed = if rest.is_empty() {
+            let index = self.get_index_for_id(first)?;
+            self.entries.remove(index)
+        } else {
+            self.get_entry_mut(first)?.remove_child_path(rest)?
+        };
+
+        for entry in self.entries.iter_mut() {
+            entry.scrub_dependency(id);
+        }
+
+        Ok(removed)
+    }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub enum FileVersion {
     Initial,
     Nested,
@@ -97,3 +165,107 @@ impl FileVersion {
         Self::Nested
     }
 }
+
+/// Used to peek at the `version` field of a stored file without requiring the
+/// rest of its shape to match the current [`WorkDataFile`] layout.
+#[derive(Deserialize)]
+pub(crate) struct FileVersionProbe {
+    pub version: FileVersion,
+}
+
+/// Upgrades a stored file from `from` to [`FileVersion::current`]. Each past
+/// version is parsed into its own dedicated, typed shape rather than bounced
+/// through an untyped `ron::Value` — round-tripping enum-bearing types (like
+/// `WorkEntryStatus`) through `Value` loses the information serde needs to
+/// tell a unit struct from a unit enum variant and fails to deserialize back.
+pub fn migrate(raw: &str, from: FileVersion) -> eyre::Result<WorkDataFile> {
+    match from {
+        FileVersion::Initial => {
+            let initial: InitialWorkDataFile =
+                ron::from_str(raw).wrap_err("Failed to parse file as the Initial format")?;
+
+            Ok(initial.into_current())
+        }
+        FileVersion::Nested => ron::from_str(raw).wrap_err("Failed to parse migrated data file"),
+    }
+}
+
+/// The shape `WorkDataFile` had back when [`FileVersion::Initial`] was
+/// current: entries with no nested `children`.
+#[derive(Deserialize)]
+struct InitialWorkDataFile {
+    entries: Vec<InitialWorkEntry>,
+}
+
+impl InitialWorkDataFile {
+    fn into_current(self) -> WorkDataFile {
+        WorkDataFile {
+            version: FileVersion::current(),
+            entries: self
+                .entries
+                .into_iter()
+                .map(InitialWorkEntry::into_current)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct InitialWorkEntry {
+    id: WorkEntryId,
+    name: String,
+    description: Option<String>,
+    created_at: DateTime<Utc>,
+    modified_at: DateTime<Utc>,
+    status: WorkEntryStatus,
+}
+
+impl InitialWorkEntry {
+    fn into_current(self) -> WorkEntry {
+        WorkEntry {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            created_at: self.created_at,
+            modified_at: self.modified_at,
+            status: self.status,
+            children: vec![],
+            time_entries: vec![],
+            priority: Priority::default(),
+            tags: HashSet::new(),
+            dependencies: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_an_initial_format_file() {
+        let raw = r#"(
+            version: Initial,
+            entries: [
+                (
+                    id: (0),
+                    name: "Write the initial migration",
+                    description: Some("Before children existed"),
+                    created_at: "2024-01-01T00:00:00Z",
+                    modified_at: "2024-01-02T00:00:00Z",
+                    status: Completed,
+                ),
+            ],
+        )"#;
+
+        let migrated = migrate(raw, FileVersion::Initial).expect("migration should succeed");
+
+        assert!(migrated.is_current());
+        assert_eq!(migrated.entries.len(), 1);
+
+        let entry = &migrated.entries[0];
+        assert_eq!(entry.name, "Write the initial migration");
+        assert_eq!(entry.status, WorkEntryStatus::Completed);
+        assert!(entry.children.is_empty());
+    }
+}
@@ -1,8 +1,16 @@
+use std::collections::HashSet;
+
 use chrono::{DateTime, Utc};
 use colored::Colorize;
+use eyre::ContextCompat;
 use serde::{Deserialize, Serialize};
 
-use crate::{work_entry_id::WorkEntryId, work_entry_status::WorkEntryStatus};
+use crate::{
+    priority::Priority,
+    time_entry::TimeEntry,
+    work_entry_id::{WorkEntryId, WorkEntryIdFull},
+    work_entry_status::WorkEntryStatus,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct WorkEntry {
@@ -13,6 +21,14 @@ pub struct WorkEntry {
     pub modified_at: DateTime<Utc>,
     pub status: WorkEntryStatus,
     pub children: Vec<WorkEntry>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: HashSet<String>,
+    #[serde(default)]
+    pub dependencies: HashSet<WorkEntryIdFull>,
 }
 
 impl WorkEntry {
@@ -25,6 +41,10 @@ impl WorkEntry {
             modified_at: Utc::now(),
             status: WorkEntryStatus::Created,
             children: vec![],
+            time_entries: vec![],
+            priority: Priority::default(),
+            tags: HashSet::new(),
+            dependencies: HashSet::new(),
         }
     }
 
@@ -33,12 +53,21 @@ impl WorkEntry {
         self.status = WorkEntryStatus::Completed;
     }
 
-    pub fn to_printable_row(&self) -> String {
+    /// Renders a single line for this entry, indented by `depth` (two spaces
+    /// per level) and addressed by its full dotted ID under `parent_id`.
+    pub fn to_printable_row(&self, depth: usize, parent_id: Option<&str>) -> String {
+        let indent = "  ".repeat(depth);
+        let full_id = match parent_id {
+            Some(parent) => format!("{parent}.{}", self.id),
+            None => self.id.to_string(),
+        };
+
         format!(
-            " {} {} {} {}",
-            self.id,
+            "{indent} {} {} {} {} {}",
+            full_id,
             "->>".green(),
             self.name.bright_cyan(),
+            self.priority.to_colored_string(),
             self.status.get_icon(),
         )
     }
@@ -46,4 +75,153 @@ impl WorkEntry {
     pub fn is_completed(&self) -> bool {
         self.status == WorkEntryStatus::Completed
     }
+
+    pub fn log_time(&mut self, logged_date: chrono::NaiveDate, hours: u16, minutes: u16) {
+        self.time_entries
+            .push(TimeEntry::new(logged_date, hours, minutes));
+        self.modified_at = Utc::now();
+    }
+
+    /// Total minutes logged directly against this entry, excluding children.
+    pub fn logged_minutes(&self) -> u32 {
+        self.time_entries
+            .iter()
+            .map(|entry| entry.hours as u32 * 60 + entry.minutes as u32)
+            .sum()
+    }
+
+    /// Total minutes logged against this entry and all of its children.
+    pub fn total_logged_minutes(&self) -> u32 {
+        self.logged_minutes()
+            + self
+                .children
+                .iter()
+                .map(WorkEntry::total_logged_minutes)
+                .sum::<u32>()
+    }
+
+    /// Drops `id`, and any ID nested beneath it, from this entry's
+    /// dependencies, recursing into children so a removed entry (or one of its
+    /// descendants) can't leave a dangling, unresolvable dependency.
+    pub fn scrub_dependency(&mut self, id: &WorkEntryIdFull) {
+        let removed = id.to_string();
+        let nested_prefix = format!("{removed}.");
+        self.dependencies.retain(|dep| {
+            let dep = dep.to_string();
+            dep != removed && !dep.starts_with(&nested_prefix)
+        });
+
+        for child in self.children.iter_mut() {
+            child.scrub_dependency(id);
+        }
+    }
+
+    /// Appends this entry (and recursively its children) as Markdown
+    /// checklist lines, indented two spaces per level of depth.
+    pub fn write_markdown(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let checkbox = if self.is_completed() { "[x]" } else { "[ ]" };
+
+        out.push_str(&format!("{indent}- {checkbox} {}", self.name));
+
+        if let Some(description) = &self.description {
+            out.push_str(&format!(" — {description}"));
+        }
+
+        out.push_str(&format!(
+            " (created {}, modified {})\n",
+            self.created_at.to_rfc3339(),
+            self.modified_at.to_rfc3339()
+        ));
+
+        for child in self.children.iter() {
+            child.write_markdown(out, depth + 1);
+        }
+    }
+
+    /// Adds a new child entry, minting its ID as one past the highest
+    /// existing child ID, starting at `0` (so the first child of `2` becomes
+    /// `2.0` and the next `2.1`).
+    pub fn add_child(
+        &mut self,
+        name: String,
+        description: Option<String>,
+        priority: Option<Priority>,
+        tags: HashSet<String>,
+        dependencies: HashSet<WorkEntryIdFull>,
+    ) {
+        let highest_num = self
+            .children
+            .iter()
+            .map(|e| &e.id)
+            .max()
+            .map(|id| id.next())
+            .unwrap_or_default();
+
+        let mut new_entry = WorkEntry::new(highest_num, name, description);
+        new_entry.priority = priority.unwrap_or_default();
+        new_entry.tags = tags;
+        new_entry.dependencies = dependencies;
+
+        self.modified_at = Utc::now();
+        self.children.push(new_entry);
+    }
+
+    /// Resolves the remaining segments of a [`WorkEntryIdFull`] against this
+    /// entry's children, descending one level per segment.
+    pub fn resolve_child_path(&self, path: &[WorkEntryId]) -> eyre::Result<&WorkEntry> {
+        match path.split_first() {
+            None => Ok(self),
+            Some((head, rest)) => {
+                let child = self
+                    .children
+                    .iter()
+                    .find(|child| &child.id == head)
+                    .wrap_err("Failed to find child entry with the provided ID")?;
+
+                child.resolve_child_path(rest)
+            }
+        }
+    }
+
+    /// Mutable counterpart of [`WorkEntry::resolve_child_path`].
+    pub fn resolve_child_path_mut(&mut self, path: &[WorkEntryId]) -> eyre::Result<&mut WorkEntry> {
+        match path.split_first() {
+            None => Ok(self),
+            Some((head, rest)) => {
+                let child = self
+                    .children
+                    .iter_mut()
+                    .find(|child| &child.id == head)
+                    .wrap_err("Failed to find child entry with the provided ID")?;
+
+                child.resolve_child_path_mut(rest)
+            }
+        }
+    }
+
+    /// Removes the descendant addressed by `path` from this entry's children.
+    pub fn remove_child_path(&mut self, path: &[WorkEntryId]) -> eyre::Result<WorkEntry> {
+        match path {
+            [] => eyre::bail!("Cannot remove an entry using an empty ID"),
+            [only] => {
+                let index = self
+                    .children
+                    .iter()
+                    .position(|child| &child.id == only)
+                    .wrap_err("Failed to find child entry with the provided ID")?;
+
+                Ok(self.children.remove(index))
+            }
+            [head, rest @ ..] => {
+                let child = self
+                    .children
+                    .iter_mut()
+                    .find(|child| &child.id == head)
+                    .wrap_err("Failed to find child entry with the provided ID")?;
+
+                child.remove_child_path(rest)
+            }
+        }
+    }
 }
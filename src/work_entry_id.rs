@@ -3,7 +3,7 @@ use std::{fmt::Display, str::FromStr};
 use eyre::Context;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
 pub struct WorkEntryIdFull(Vec<WorkEntryId>);
 
 impl FromStr for WorkEntryIdFull {
@@ -19,7 +19,27 @@ impl FromStr for WorkEntryIdFull {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+impl Display for WorkEntryIdFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let joined = self
+            .0
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        write!(f, "{joined}")
+    }
+}
+
+impl WorkEntryIdFull {
+    /// Splits off the top-level segment of the path from the segments that
+    /// address its descendants, e.g. `2.1.3` splits into `2` and `[1, 3]`.
+    pub fn split_first(&self) -> Option<(&WorkEntryId, &[WorkEntryId])> {
+        self.0.split_first()
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
 pub struct WorkEntryId(usize);
 
 impl FromStr for WorkEntryId {